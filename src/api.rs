@@ -0,0 +1,84 @@
+use reqwest::Client;
+use reqwest::Response;
+
+use crate::error::LifxError;
+use crate::model::Light;
+use crate::model::SetState;
+use crate::model::SetStateResult;
+use crate::model::UpdateStatus;
+
+const LIGHTS_ALL_URL: &str = "https://api.lifx.com/v1/lights/all";
+
+pub(crate) async fn get_lights(client: &Client, token: &str) -> Result<Vec<Light>, LifxError> {
+    let resp = client.get(LIGHTS_ALL_URL).bearer_auth(token).send().await?;
+    let resp = check_status(resp).await?;
+
+    parse_json(resp).await
+}
+
+/// Sets state for the given selector, e.g. `id:<id>`, `group:<name>` or `all`.
+pub(crate) async fn set_state(
+    client: &Client,
+    token: &str,
+    selector: &str,
+    state: &SetState,
+) -> Result<Vec<SetStateResult>, LifxError> {
+    let url = format!("https://api.lifx.com/v1/lights/{}/state", selector);
+
+    let resp = client
+        .put(url)
+        .bearer_auth(token)
+        .json(state)
+        .send()
+        .await?;
+    let resp = check_status(resp).await?;
+    let status = resp.status();
+
+    let body: crate::model::SetStateResponse = parse_json(resp).await?;
+
+    let results = match body.results {
+        Some(results) => results,
+        None => {
+            let message = body
+                .error
+                .unwrap_or_else(|| "the LIFX API returned no results".to_owned());
+
+            return Err(LifxError::Api { status, message });
+        }
+    };
+
+    if !results.is_empty() && results.iter().all(|r| r.status != UpdateStatus::Ok) {
+        return Err(LifxError::Unreachable(results));
+    }
+
+    Ok(results)
+}
+
+/// Maps HTTP-level failures onto [`LifxError`], passing the response through
+/// unconsumed when the status was a success.
+async fn check_status(resp: Response) -> Result<Response, LifxError> {
+    let status = resp.status();
+
+    if status.is_success() {
+        return Ok(resp);
+    }
+
+    match status.as_u16() {
+        401 => Err(LifxError::Unauthorized),
+        404 => Err(LifxError::NotFound),
+        429 => Err(LifxError::RateLimited),
+        _ => {
+            let message = resp.text().await.unwrap_or_default();
+            Err(LifxError::Api { status, message })
+        }
+    }
+}
+
+async fn parse_json<T>(resp: Response) -> Result<T, LifxError>
+where
+    T: serde::de::DeserializeOwned,
+{
+    let body = resp.text().await?;
+
+    serde_json::from_str(&body).map_err(LifxError::Parse)
+}
@@ -0,0 +1,72 @@
+use std::collections::HashMap;
+use std::env;
+use std::error::Error;
+use std::fs;
+use std::path::PathBuf;
+
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::model::SetState;
+
+/// Layered configuration for the controller: the API token plus
+/// user-defined aliases (friendly name -> real LIFX selector) and scenes
+/// (friendly name -> a [`SetState`] to apply in one call).
+#[derive(Deserialize, Serialize, Debug, Default)]
+pub(crate) struct Config {
+    pub(crate) token: Option<String>,
+    #[serde(default)]
+    pub(crate) aliases: HashMap<String, String>,
+    #[serde(default)]
+    pub(crate) scenes: HashMap<String, SetState>,
+}
+
+/// Loads the config file from `path_override`, `LIFX_CONFIG_PATH`, or the
+/// platform config dir, in that order. A missing file is not an error - it
+/// just means there are no aliases/scenes and the token must come from
+/// `LIFX_TOKEN`.
+pub(crate) fn load(path_override: Option<&str>) -> Result<Config, Box<dyn Error>> {
+    let path = resolve_path(path_override)?;
+
+    if !path.exists() {
+        return Ok(Config::default());
+    }
+
+    let contents = fs::read_to_string(&path)?;
+    let config = toml::from_str(&contents)?;
+
+    Ok(config)
+}
+
+fn resolve_path(path_override: Option<&str>) -> Result<PathBuf, Box<dyn Error>> {
+    if let Some(path) = path_override {
+        return Ok(PathBuf::from(path));
+    }
+
+    if let Ok(path) = env::var("LIFX_CONFIG_PATH") {
+        return Ok(PathBuf::from(path));
+    }
+
+    dirs::config_dir()
+        .map(|dir| dir.join("lifx-controller").join("config.toml"))
+        .ok_or_else(|| "could not determine the platform config directory".into())
+}
+
+/// `LIFX_TOKEN` takes priority over the config file, matching the
+/// environment-variable behaviour the controller already had.
+pub(crate) fn token(config: &Config) -> Result<String, Box<dyn Error>> {
+    env::var("LIFX_TOKEN")
+        .ok()
+        .or_else(|| config.token.clone())
+        .ok_or_else(|| "no LIFX_TOKEN set and no token in the config file".into())
+}
+
+/// Resolves a `--selector` value against the configured aliases, falling
+/// back to treating it as a literal LIFX selector when there's no match.
+pub(crate) fn resolve_selector<'a>(config: &'a Config, selector: &'a str) -> &'a str {
+    config
+        .aliases
+        .get(selector)
+        .map(String::as_str)
+        .unwrap_or(selector)
+}
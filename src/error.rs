@@ -0,0 +1,89 @@
+use std::error::Error;
+use std::fmt;
+
+use reqwest::StatusCode;
+
+use crate::model::SetStateResult;
+
+/// Errors that can occur while talking to the LIFX HTTP API, distinguishing
+/// the common failure kinds from a generic "something went wrong" so
+/// callers (and the bridge/watch/serve modes) can match on them.
+#[derive(Debug)]
+pub(crate) enum LifxError {
+    /// The API token was missing or rejected (HTTP 401).
+    Unauthorized,
+    /// The request was rate limited (HTTP 429).
+    RateLimited,
+    /// The selector didn't match any lights (HTTP 404).
+    NotFound,
+    /// The API returned a non-success status not covered above, along with
+    /// whatever body it sent back, or a successful status whose body carried
+    /// no results (with its `error` message, if any).
+    Api { status: StatusCode, message: String },
+    /// Sending the request or reading the response failed at the transport
+    /// level.
+    Http(reqwest::Error),
+    /// The response body wasn't valid JSON, or didn't match the shape we
+    /// expected.
+    Parse(serde_json::Error),
+    /// The request succeeded, but every matched light reported `timed_out`
+    /// or `offline` rather than `ok`.
+    Unreachable(Vec<SetStateResult>),
+}
+
+impl fmt::Display for LifxError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LifxError::Unauthorized => write!(f, "unauthorized - check the LIFX API token"),
+            LifxError::RateLimited => write!(f, "rate limited by the LIFX API"),
+            LifxError::NotFound => write!(f, "no lights matched the selector"),
+            LifxError::Api { status, message } => {
+                write!(f, "LIFX API returned {}: {}", status, message)
+            }
+            LifxError::Http(err) => write!(f, "request to the LIFX API failed: {}", err),
+            LifxError::Parse(err) => write!(f, "could not parse the LIFX API response: {}", err),
+            LifxError::Unreachable(results) => {
+                write!(f, "no light responded: ")?;
+
+                for (i, r) in results.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{} ({})", r.label, r.status)?;
+                }
+
+                Ok(())
+            }
+        }
+    }
+}
+
+impl Error for LifxError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            LifxError::Http(err) => Some(err),
+            LifxError::Parse(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<reqwest::Error> for LifxError {
+    fn from(err: reqwest::Error) -> Self {
+        LifxError::Http(err)
+    }
+}
+
+impl LifxError {
+    /// The HTTP status a management API caller should see for this error.
+    pub(crate) fn status_code(&self) -> StatusCode {
+        match self {
+            LifxError::Unauthorized => StatusCode::UNAUTHORIZED,
+            LifxError::RateLimited => StatusCode::TOO_MANY_REQUESTS,
+            LifxError::NotFound => StatusCode::NOT_FOUND,
+            LifxError::Api { status, .. } => *status,
+            LifxError::Http(_) | LifxError::Parse(_) => StatusCode::BAD_GATEWAY,
+            LifxError::Unreachable(_) => StatusCode::SERVICE_UNAVAILABLE,
+        }
+    }
+}
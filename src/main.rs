@@ -1,30 +1,36 @@
-use std::env;
 use std::error::Error;
+use std::time::Duration;
 
 use clap::App;
 use clap::Arg;
 use clap::SubCommand;
-use serde::Deserialize;
-use serde::Serialize;
+
+mod api;
+mod config;
+mod error;
+mod model;
+mod mqtt;
+mod serve;
+mod watch;
+
+use model::Power;
+use model::SetState;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn Error>> {
     let client = reqwest::Client::new();
 
-    let token = env::var("LIFX_TOKEN")?;
-
-    let lights = client
-        .get("https://api.lifx.com/v1/lights/all")
-        .bearer_auth(&token)
-        .send()
-        .await?
-        .json::<Vec<Light>>()
-        .await?;
-
     let matches = App::new("LiFX Controller")
         .version("1.0")
         .author("David Muir <hey@davidmuir.co>")
         .about("Allows controlling the Lifx bulbs in my home")
+        .arg(
+            Arg::with_name("config")
+                .long("config")
+                .takes_value(true)
+                .global(true)
+                .help("Path to the config file (defaults to the platform config dir)"),
+        )
         .subcommand(
             SubCommand::with_name("set")
                 .about("Updates state of one or more lights")
@@ -47,167 +53,211 @@ async fn main() -> Result<(), Box<dyn Error>> {
                         .long("selector")
                         .short("s")
                         .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("scene")
+                        .long("scene")
+                        .takes_value(true)
+                        .help("Applies a named scene from the config file instead of --on/--off/--colour/--brightness"),
                 ),
         )
         .subcommand(SubCommand::with_name("list").about("Lists all available lights"))
+        .subcommand(
+            SubCommand::with_name("bridge")
+                .about("Runs a long-lived MQTT bridge for the LIFX bulbs")
+                .arg(
+                    Arg::with_name("mqtt-host")
+                        .long("mqtt-host")
+                        .takes_value(true)
+                        .env("LIFX_MQTT_HOST")
+                        .default_value("localhost"),
+                )
+                .arg(
+                    Arg::with_name("mqtt-port")
+                        .long("mqtt-port")
+                        .takes_value(true)
+                        .env("LIFX_MQTT_PORT")
+                        .default_value("1883"),
+                )
+                .arg(
+                    Arg::with_name("mqtt-username")
+                        .long("mqtt-username")
+                        .takes_value(true)
+                        .env("LIFX_MQTT_USERNAME"),
+                )
+                .arg(
+                    Arg::with_name("mqtt-password")
+                        .long("mqtt-password")
+                        .takes_value(true)
+                        .env("LIFX_MQTT_PASSWORD"),
+                )
+                .arg(
+                    Arg::with_name("base-topic")
+                        .long("base-topic")
+                        .takes_value(true)
+                        .env("LIFX_MQTT_BASE_TOPIC")
+                        .default_value("lifx"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("watch")
+                .about("Polls the LIFX API and logs changes to the lights")
+                .arg(
+                    Arg::with_name("interval")
+                        .long("interval")
+                        .short("i")
+                        .takes_value(true)
+                        .default_value("5")
+                        .help("Polling interval in seconds"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("serve")
+                .about("Runs a local HTTP API for listing and controlling the lights")
+                .arg(
+                    Arg::with_name("addr")
+                        .long("addr")
+                        .takes_value(true)
+                        .env("LIFX_SERVE_ADDR")
+                        .default_value("127.0.0.1:8080"),
+                )
+                .arg(
+                    Arg::with_name("cache-interval")
+                        .long("cache-interval")
+                        .takes_value(true)
+                        .default_value("5")
+                        .help("How often to refresh the /lights cache, in seconds"),
+                ),
+        )
         .get_matches();
 
+    let config = config::load(matches.value_of("config"))?;
+    let token = config::token(&config)?;
+
     if let Some(matches) = matches.subcommand_matches("set") {
-        let state = SetState {
-            power: if matches.is_present("on") {
-                Some(Power::On)
-            } else if matches.is_present("off") {
-                Some(Power::Off)
-            } else {
-                None
-            },
-            brightness: if let Some(b) = matches.value_of("brightness") {
-                Some(b.parse::<f32>()?)
-            } else {
-                None
-            },
-            color: if let Some(c) = matches.value_of("colour") {
-                Some(c.into())
-            } else {
-                None
-            },
+        let state = if let Some(scene) = matches.value_of("scene") {
+            config
+                .scenes
+                .get(scene)
+                .cloned()
+                .ok_or_else(|| format!("no scene named '{}' in the config file", scene))?
+        } else {
+            SetState {
+                power: if matches.is_present("on") {
+                    Some(Power::On)
+                } else if matches.is_present("off") {
+                    Some(Power::Off)
+                } else {
+                    None
+                },
+                brightness: if let Some(b) = matches.value_of("brightness") {
+                    Some(b.parse::<f32>()?)
+                } else {
+                    None
+                },
+                color: if let Some(c) = matches.value_of("colour") {
+                    Some(c.into())
+                } else {
+                    None
+                },
+            }
         };
 
         if let Some(selector) = matches.value_of("selector") {
-            for l in lights.into_iter().filter(|l| {
-                l.label
-                    .to_ascii_lowercase()
-                    .contains(&selector.to_ascii_lowercase())
-                    || l.group
-                        .name
-                        .to_ascii_lowercase()
-                        .contains(&selector.to_ascii_lowercase())
-            }) {
-                print!("Updating {} in {}", l.label, l.group.name);
+            let real_selector = config::resolve_selector(&config, selector);
 
-                let mut url = "https://api.lifx.com/v1/lights/id:".to_owned();
+            if real_selector != selector {
+                println!("Setting {} ({})", selector, real_selector);
 
-                url.push_str(&l.id);
+                match api::set_state(&client, &token, real_selector, &state).await {
+                    Ok(results) => {
+                        for r in results {
+                            println!(" - {}: {}", r.label, r.status);
+                        }
+                    }
+                    Err(err) => println!("Failed to update {}: {}", selector, err),
+                }
+            } else {
+                let lights = api::get_lights(&client, &token).await?;
 
-                url.push_str("/state");
+                for l in lights.into_iter().filter(|l| {
+                    l.label
+                        .to_ascii_lowercase()
+                        .contains(&selector.to_ascii_lowercase())
+                        || l.group
+                            .name
+                            .to_ascii_lowercase()
+                            .contains(&selector.to_ascii_lowercase())
+                }) {
+                    print!("Updating {} in {}", l.label, l.group.name);
 
-                let resp = client
-                    .put(url)
-                    .bearer_auth(&token)
-                    .json(&state)
-                    .send()
-                    .await?
-                    .json::<SetStateResponse>()
-                    .await?;
+                    let id_selector = format!("id:{}", l.id);
 
-                if let Some(results) = resp.results {
-                    for r in results {
-                        print!(" - {}", r.status);
+                    match api::set_state(&client, &token, &id_selector, &state).await {
+                        Ok(results) => {
+                            for r in results {
+                                print!(" - {}", r.status);
+                            }
+                        }
+                        Err(err) => print!(" - failed: {}", err),
                     }
-                } else {
-                    println!("Something went wrong - {:#?}", resp);
-                }
 
-                println!()
+                    println!()
+                }
             }
         } else {
             println!("Setting all lights");
 
-            let url = "https://api.lifx.com/v1/lights/all";
-
-            let resp = client
-                .put(url)
-                .bearer_auth(&token)
-                .json(&state)
-                .send()
-                .await?
-                .json::<SetStateResponse>()
-                .await?;
-
-            if let Some(results) = resp.results {
-                for r in results {
-                    println!(" - {}", r.status);
+            match api::set_state(&client, &token, "all", &state).await {
+                Ok(results) => {
+                    for r in results {
+                        println!(" - {}: {}", r.label, r.status);
+                    }
                 }
-            } else {
-                println!("Something went wrong - {:#?}", resp);
+                Err(err) => println!("Failed to update lights: {}", err),
             }
 
             println!()
         };
     } else if let Some(_matches) = matches.subcommand_matches("list") {
+        let lights = api::get_lights(&client, &token).await?;
+
         for light in lights.into_iter() {
             println!(
                 "{} - {} - power:{:?}, brightness:{}, temperature:{}k",
                 light.label, light.group.name, light.power, light.brightness, light.color.kelvin
             );
         }
-    }
-
-    Ok(())
-}
-
-#[derive(Serialize, Debug)]
-struct SetState {
-    power: Option<Power>,
-    brightness: Option<f32>,
-    color: Option<String>,
-}
+    } else if let Some(matches) = matches.subcommand_matches("bridge") {
+        let opts = mqtt::BridgeOptions {
+            host: matches.value_of("mqtt-host").unwrap().to_owned(),
+            port: matches.value_of("mqtt-port").unwrap().parse()?,
+            username: matches.value_of("mqtt-username").map(str::to_owned),
+            password: matches.value_of("mqtt-password").map(str::to_owned),
+            base_topic: matches.value_of("base-topic").unwrap().to_owned(),
+        };
 
-#[derive(Deserialize, Debug)]
-struct SetStateResponse {
-    results: Option<Vec<SetStateResult>>,
-    error: Option<String>,
-}
+        mqtt::run_bridge(client, token, opts).await?;
+    } else if let Some(matches) = matches.subcommand_matches("watch") {
+        watch::init_logging()?;
 
-#[derive(Deserialize, Debug)]
-struct SetStateResult {
-    id: String,
-    label: String,
-    status: String,
-}
+        let interval = matches.value_of("interval").unwrap().parse::<u64>()?;
 
-#[derive(Deserialize, Debug)]
-struct Light {
-    id: String,
-    uuid: String,
-    label: String,
-    connected: bool,
-    power: Power,
-    color: Colour,
-    brightness: f32,
-    group: Group,
-    location: Group,
-    product: Product,
-    last_seen: String,
-    seconds_since_seen: u32,
-}
+        let opts = watch::WatchOptions {
+            interval: Duration::from_secs(interval),
+        };
 
-#[derive(Deserialize, Debug)]
-struct Product {
-    name: String,
-    identifier: String,
-    company: String,
-    vendor_id: u8,
-    product_id: u32,
-}
+        watch::run_watch(client, token, opts).await?;
+    } else if let Some(matches) = matches.subcommand_matches("serve") {
+        let cache_interval = matches.value_of("cache-interval").unwrap().parse::<u64>()?;
 
-#[derive(Deserialize, Debug)]
-struct Group {
-    id: String,
-    name: String,
-}
+        let opts = serve::ServeOptions {
+            addr: matches.value_of("addr").unwrap().parse()?,
+            cache_interval: Duration::from_secs(cache_interval),
+        };
 
-#[derive(Deserialize, Serialize, Debug)]
-enum Power {
-    #[serde(rename = "on")]
-    On,
-    #[serde(rename = "off")]
-    Off,
-}
+        serve::run_serve(client, token, opts).await?;
+    }
 
-#[derive(Deserialize, Debug)]
-struct Colour {
-    hue: u32,
-    saturation: f32,
-    kelvin: u32,
+    Ok(())
 }
@@ -0,0 +1,87 @@
+use serde::Deserialize;
+use serde::Serialize;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub(crate) struct SetState {
+    pub(crate) power: Option<Power>,
+    pub(crate) brightness: Option<f32>,
+    pub(crate) color: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct SetStateResponse {
+    pub(crate) results: Option<Vec<SetStateResult>>,
+    pub(crate) error: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+pub(crate) struct SetStateResult {
+    pub(crate) id: String,
+    pub(crate) label: String,
+    pub(crate) status: UpdateStatus,
+}
+
+/// The outcome LIFX reports for a single light after a state change.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum UpdateStatus {
+    Ok,
+    TimedOut,
+    Offline,
+}
+
+impl std::fmt::Display for UpdateStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UpdateStatus::Ok => write!(f, "ok"),
+            UpdateStatus::TimedOut => write!(f, "timed_out"),
+            UpdateStatus::Offline => write!(f, "offline"),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub(crate) struct Light {
+    pub(crate) id: String,
+    pub(crate) uuid: String,
+    pub(crate) label: String,
+    pub(crate) connected: bool,
+    pub(crate) power: Power,
+    pub(crate) color: Colour,
+    pub(crate) brightness: f32,
+    pub(crate) group: Group,
+    pub(crate) location: Group,
+    pub(crate) product: Product,
+    pub(crate) last_seen: String,
+    pub(crate) seconds_since_seen: u32,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub(crate) struct Product {
+    pub(crate) name: String,
+    pub(crate) identifier: String,
+    pub(crate) company: String,
+    pub(crate) vendor_id: u8,
+    pub(crate) product_id: u32,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Group {
+    pub(crate) id: String,
+    pub(crate) name: String,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Power {
+    #[serde(rename = "on")]
+    On,
+    #[serde(rename = "off")]
+    Off,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub(crate) struct Colour {
+    pub(crate) hue: u32,
+    pub(crate) saturation: f32,
+    pub(crate) kelvin: u32,
+}
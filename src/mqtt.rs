@@ -0,0 +1,147 @@
+use std::error::Error;
+use std::time::Duration;
+
+use reqwest::Client;
+use rumqttc::AsyncClient;
+use rumqttc::Event;
+use rumqttc::MqttOptions;
+use rumqttc::Packet;
+use rumqttc::QoS;
+
+use crate::api;
+use crate::model::Light;
+use crate::model::SetState;
+
+/// Options for connecting to the MQTT broker, gathered from the `bridge`
+/// subcommand's flags/env.
+pub(crate) struct BridgeOptions {
+    pub(crate) host: String,
+    pub(crate) port: u16,
+    pub(crate) username: Option<String>,
+    pub(crate) password: Option<String>,
+    pub(crate) base_topic: String,
+}
+
+/// Runs the MQTT bridge until the process is killed. Connects to the
+/// configured broker, subscribes to `<base_topic>/+/+/set`, publishes the
+/// current state of every light to `<base_topic>/<group>/<label>/status` on
+/// startup, and republishes the status of the lights a `set` message matched
+/// after applying it.
+pub(crate) async fn run_bridge(
+    client: Client,
+    token: String,
+    opts: BridgeOptions,
+) -> Result<(), Box<dyn Error>> {
+    let mut mqtt_opts = MqttOptions::new("lifx-controller", &opts.host, opts.port);
+    mqtt_opts.set_keep_alive(Duration::from_secs(30));
+
+    if let (Some(username), Some(password)) = (&opts.username, &opts.password) {
+        mqtt_opts.set_credentials(username, password);
+    }
+
+    let (mqtt_client, mut event_loop) = AsyncClient::new(mqtt_opts, 10);
+
+    let set_filter = format!("{}/+/+/set", opts.base_topic);
+    mqtt_client.subscribe(&set_filter, QoS::AtLeastOnce).await?;
+
+    let lights = api::get_lights(&client, &token).await?;
+    for light in &lights {
+        publish_status(&mqtt_client, &opts.base_topic, light).await?;
+    }
+
+    loop {
+        let notification = match event_loop.poll().await {
+            Ok(notification) => notification,
+            Err(err) => {
+                eprintln!("MQTT connection error, retrying: {}", err);
+                continue;
+            }
+        };
+
+        if let Event::Incoming(Packet::Publish(publish)) = notification {
+            let (group, label) = match parse_set_topic(&publish.topic, &opts.base_topic) {
+                Some(parts) => parts,
+                None => continue,
+            };
+
+            let state: SetState = match serde_json::from_slice(&publish.payload) {
+                Ok(state) => state,
+                Err(err) => {
+                    eprintln!("Ignoring invalid payload on {}: {}", publish.topic, err);
+                    continue;
+                }
+            };
+
+            let lights = match api::get_lights(&client, &token).await {
+                Ok(lights) => lights,
+                Err(err) => {
+                    eprintln!("Failed to fetch lights for {}/{}: {}", group, label, err);
+                    continue;
+                }
+            };
+
+            let matched_ids: Vec<String> = lights
+                .iter()
+                .filter(|l| l.group.name == group && l.label == label)
+                .map(|l| l.id.clone())
+                .collect();
+
+            if matched_ids.is_empty() {
+                eprintln!("No light found for {}/{}", group, label);
+                continue;
+            }
+
+            for id in &matched_ids {
+                let selector = format!("id:{}", id);
+
+                if let Err(err) = api::set_state(&client, &token, &selector, &state).await {
+                    eprintln!("Failed to update {}/{}: {}", group, label, err);
+                }
+            }
+
+            let lights = match api::get_lights(&client, &token).await {
+                Ok(lights) => lights,
+                Err(err) => {
+                    eprintln!("Failed to refresh lights for {}/{}: {}", group, label, err);
+                    continue;
+                }
+            };
+
+            for light in lights.iter().filter(|l| matched_ids.contains(&l.id)) {
+                if let Err(err) = publish_status(&mqtt_client, &opts.base_topic, light).await {
+                    eprintln!("Failed to publish status for {}: {}", light.label, err);
+                }
+            }
+        }
+    }
+}
+
+async fn publish_status(
+    mqtt_client: &AsyncClient,
+    base_topic: &str,
+    light: &Light,
+) -> Result<(), Box<dyn Error>> {
+    let topic = format!("{}/{}/{}/status", base_topic, light.group.name, light.label);
+    let payload = serde_json::to_vec(light)?;
+
+    mqtt_client
+        .publish(topic, QoS::AtLeastOnce, true, payload)
+        .await?;
+
+    Ok(())
+}
+
+/// Splits `<base_topic>/<group>/<label>/set` into `(group, label)`.
+fn parse_set_topic<'a>(topic: &'a str, base_topic: &str) -> Option<(&'a str, &'a str)> {
+    let rest = topic.strip_prefix(base_topic)?.strip_prefix('/')?;
+    let mut parts = rest.splitn(3, '/');
+
+    let group = parts.next()?;
+    let label = parts.next()?;
+
+    if parts.next()? != "set" {
+        return None;
+    }
+
+    Some((group, label))
+}
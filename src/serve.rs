@@ -0,0 +1,155 @@
+use std::convert::Infallible;
+use std::error::Error;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use reqwest::Client;
+use serde::Serialize;
+use tokio::sync::RwLock;
+use warp::http::StatusCode;
+use warp::Filter;
+use warp::Rejection;
+use warp::Reply;
+
+use crate::api;
+use crate::error::LifxError;
+use crate::model::Light;
+use crate::model::SetState;
+
+/// Options for the `serve` subcommand's local management API.
+pub(crate) struct ServeOptions {
+    pub(crate) addr: SocketAddr,
+    pub(crate) cache_interval: Duration,
+}
+
+/// Runs a small HTTP server so other processes on the LAN can list and
+/// control the lights without holding `LIFX_TOKEN` themselves.
+///
+/// `GET /lights` serves a cache that's refreshed on `opts.cache_interval`.
+/// `PUT /lights/:selector/state` forwards straight to the LIFX cloud API
+/// through the same `api::set_state` call the `set` subcommand uses. The
+/// selector is passed through as-is: config aliases and the `set`
+/// subcommand's label/group name matching are not applied.
+pub(crate) async fn run_serve(
+    client: Client,
+    token: String,
+    opts: ServeOptions,
+) -> Result<(), Box<dyn Error>> {
+    let cache = Arc::new(RwLock::new(api::get_lights(&client, &token).await?));
+
+    tokio::spawn(refresh_cache(
+        client.clone(),
+        token.clone(),
+        cache.clone(),
+        opts.cache_interval,
+    ));
+
+    let get_lights = warp::path("lights")
+        .and(warp::path::end())
+        .and(warp::get())
+        .and(with_cache(cache))
+        .and_then(list_lights);
+
+    let set_state = warp::path("lights")
+        .and(warp::path::param::<String>())
+        .and(warp::path("state"))
+        .and(warp::path::end())
+        .and(warp::put())
+        .and(warp::body::json())
+        .and(with_client(client))
+        .and(with_token(token))
+        .and_then(set_light_state);
+
+    let routes = get_lights.or(set_state).recover(handle_rejection);
+
+    warp::serve(routes).run(opts.addr).await;
+
+    Ok(())
+}
+
+async fn refresh_cache(
+    client: Client,
+    token: String,
+    cache: Arc<RwLock<Vec<Light>>>,
+    interval: Duration,
+) {
+    let mut ticker = tokio::time::interval(interval);
+
+    loop {
+        ticker.tick().await;
+
+        match api::get_lights(&client, &token).await {
+            Ok(lights) => *cache.write().await = lights,
+            Err(err) => eprintln!("Failed to refresh light cache: {}", err),
+        }
+    }
+}
+
+async fn list_lights(cache: Arc<RwLock<Vec<Light>>>) -> Result<impl Reply, Infallible> {
+    let lights = cache.read().await.clone();
+
+    Ok(warp::reply::json(&lights))
+}
+
+async fn set_light_state(
+    selector: String,
+    state: SetState,
+    client: Client,
+    token: String,
+) -> Result<impl Reply, Rejection> {
+    match api::set_state(&client, &token, &selector, &state).await {
+        Ok(results) => Ok(warp::reply::json(&results)),
+        Err(err) => Err(warp::reject::custom(ApiError(err))),
+    }
+}
+
+#[derive(Debug)]
+struct ApiError(LifxError);
+
+impl warp::reject::Reject for ApiError {}
+
+#[derive(Serialize)]
+struct ErrorBody {
+    error: String,
+}
+
+/// Turns a rejected `ApiError` into a JSON body with the status the
+/// underlying `LifxError` maps to, so a LAN caller can distinguish e.g. an
+/// auth failure from a genuine server error instead of seeing an opaque 500.
+async fn handle_rejection(err: Rejection) -> Result<impl Reply, Infallible> {
+    if let Some(ApiError(lifx_err)) = err.find::<ApiError>() {
+        let status = lifx_err.status_code();
+        let body = ErrorBody {
+            error: lifx_err.to_string(),
+        };
+
+        return Ok(warp::reply::with_status(warp::reply::json(&body), status));
+    }
+
+    let status = if err.is_not_found() {
+        StatusCode::NOT_FOUND
+    } else {
+        StatusCode::INTERNAL_SERVER_ERROR
+    };
+
+    let body = ErrorBody {
+        error: format!("{:?}", err),
+    };
+
+    Ok(warp::reply::with_status(warp::reply::json(&body), status))
+}
+
+fn with_cache(
+    cache: Arc<RwLock<Vec<Light>>>,
+) -> impl Filter<Extract = (Arc<RwLock<Vec<Light>>>,), Error = Infallible> + Clone {
+    warp::any().map(move || cache.clone())
+}
+
+fn with_client(client: Client) -> impl Filter<Extract = (Client,), Error = Infallible> + Clone {
+    warp::any().map(move || client.clone())
+}
+
+fn with_token(token: String) -> impl Filter<Extract = (String,), Error = Infallible> + Clone {
+    warp::any().map(move || token.clone())
+}
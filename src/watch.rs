@@ -0,0 +1,106 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::time::Duration;
+
+use log::debug;
+use log::trace;
+use log::warn;
+use reqwest::Client;
+
+use crate::api;
+use crate::model::Light;
+
+/// Options for the `watch` subcommand's polling loop.
+pub(crate) struct WatchOptions {
+    pub(crate) interval: Duration,
+}
+
+/// Polls `GET /v1/lights/all` on `opts.interval`, diffing each response
+/// against the previous one and logging lights that appeared, disappeared,
+/// or changed power/brightness/color. A failed poll is logged as a warning
+/// and retried on the next tick rather than aborting the loop.
+pub(crate) async fn run_watch(
+    client: Client,
+    token: String,
+    opts: WatchOptions,
+) -> Result<(), Box<dyn Error>> {
+    let mut cached: HashMap<String, Light> = HashMap::new();
+    let mut ticker = tokio::time::interval(opts.interval);
+
+    loop {
+        ticker.tick().await;
+
+        let lights = match api::get_lights(&client, &token).await {
+            Ok(lights) => lights,
+            Err(err) => {
+                warn!("Failed to poll lights: {}", err);
+                continue;
+            }
+        };
+
+        debug!("Polled {} lights", lights.len());
+
+        let mut seen = std::collections::HashSet::new();
+
+        for light in &lights {
+            trace!("{:?}", light);
+            seen.insert(light.id.clone());
+
+            match cached.get(&light.id) {
+                None => {
+                    debug!("Light appeared: {} ({})", light.label, light.id);
+                }
+                Some(previous) => {
+                    if previous.power != light.power {
+                        debug!(
+                            "Light {} power changed: {:?} -> {:?}",
+                            light.label, previous.power, light.power
+                        );
+                    }
+
+                    if previous.brightness != light.brightness {
+                        debug!(
+                            "Light {} brightness changed: {} -> {}",
+                            light.label, previous.brightness, light.brightness
+                        );
+                    }
+
+                    if previous.color != light.color {
+                        debug!(
+                            "Light {} color changed: {:?} -> {:?}",
+                            light.label, previous.color, light.color
+                        );
+                    }
+                }
+            }
+        }
+
+        for (id, light) in &cached {
+            if !seen.contains(id) {
+                debug!("Light disappeared: {} ({})", light.label, id);
+            }
+        }
+
+        cached = lights.into_iter().map(|l| (l.id.clone(), l)).collect();
+    }
+}
+
+/// Sets up a `fern` dispatcher so `RUST_LOG` controls the verbosity of the
+/// `trace!`/`debug!`/`warn!` calls made while polling. Defaults to `trace`
+/// when `RUST_LOG` is unset so raw responses are visible out of the box.
+pub(crate) fn init_logging() -> Result<(), fern::InitError> {
+    let level = std::env::var("RUST_LOG")
+        .ok()
+        .and_then(|level| level.parse().ok())
+        .unwrap_or(log::LevelFilter::Trace);
+
+    fern::Dispatch::new()
+        .format(|out, message, record| {
+            out.finish(format_args!("[{}] {}", record.level(), message))
+        })
+        .level(level)
+        .chain(std::io::stdout())
+        .apply()?;
+
+    Ok(())
+}